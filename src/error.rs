@@ -0,0 +1,40 @@
+use std::fmt;
+
+#[cfg(target_os = "macos")]
+use core_graphics::base::CGError;
+
+#[derive(Debug)]
+pub enum XCapError {
+    Error(String),
+    #[cfg(target_os = "macos")]
+    CoreGraphicsDisplayCGError(CGError),
+}
+
+impl XCapError {
+    pub fn new<S: ToString>(err: S) -> XCapError {
+        XCapError::Error(err.to_string())
+    }
+}
+
+impl fmt::Display for XCapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            XCapError::Error(err) => write!(f, "{err}"),
+            #[cfg(target_os = "macos")]
+            XCapError::CoreGraphicsDisplayCGError(err) => {
+                write!(f, "CoreGraphics display error: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for XCapError {}
+
+#[cfg(target_os = "macos")]
+impl From<CGError> for XCapError {
+    fn from(err: CGError) -> Self {
+        XCapError::CoreGraphicsDisplayCGError(err)
+    }
+}
+
+pub type XCapResult<T> = Result<T, XCapError>;
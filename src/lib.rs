@@ -0,0 +1,18 @@
+mod error;
+
+#[cfg(target_os = "macos")]
+mod macos;
+
+#[cfg(target_os = "macos")]
+mod monitor;
+#[cfg(target_os = "macos")]
+mod video_recorder;
+
+pub use error::{XCapError, XCapResult};
+
+#[cfg(target_os = "macos")]
+pub use macos::{CaptureStream, FrameHandler, GammaRamp, VideoMode};
+#[cfg(target_os = "macos")]
+pub use monitor::Monitor;
+#[cfg(target_os = "macos")]
+pub use video_recorder::VideoRecorder;
@@ -0,0 +1,47 @@
+use core_graphics::display::{kCGWindowImageDefault, CGDisplay, CGRect, CGWindowID, CGWindowListOption};
+use core_graphics::image::CGImage;
+use image::RgbaImage;
+
+use crate::error::{XCapError, XCapResult};
+
+fn cg_image_to_rgba_image(cg_image: CGImage) -> XCapResult<RgbaImage> {
+    let width = cg_image.width();
+    let height = cg_image.height();
+    let bytes_per_row = cg_image.bytes_per_row();
+    let cg_data = cg_image.data();
+    let bytes = cg_data.bytes();
+
+    // `CGWindowListCreateImage` hands back BGRA rows, each padded out to `bytes_per_row`.
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for row in 0..height {
+        let row_start = row * bytes_per_row;
+        for col in 0..width {
+            let pixel_start = row_start + col * 4;
+            rgba.extend_from_slice(&[
+                bytes[pixel_start + 2],
+                bytes[pixel_start + 1],
+                bytes[pixel_start],
+                bytes[pixel_start + 3],
+            ]);
+        }
+    }
+
+    RgbaImage::from_raw(width as u32, height as u32, rgba)
+        .ok_or_else(|| XCapError::new("RgbaImage::from_raw failed"))
+}
+
+pub(crate) fn capture(
+    cg_rect: CGRect,
+    window_list_option: CGWindowListOption,
+    window_id: CGWindowID,
+) -> XCapResult<RgbaImage> {
+    let cg_image = CGDisplay::screenshot(
+        cg_rect,
+        window_list_option,
+        window_id,
+        kCGWindowImageDefault,
+    )
+    .ok_or_else(|| XCapError::new("Capture display image failed"))?;
+
+    cg_image_to_rgba_image(cg_image)
+}
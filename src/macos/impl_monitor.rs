@@ -1,9 +1,24 @@
-use std::{collections::HashMap, io::Error};
+use std::{
+    collections::HashMap,
+    ffi::{c_char, c_void, CString},
+    io::Error,
+    sync::Mutex,
+};
 
+use block2::RcBlock;
+use core_foundation::{base::TCFType, boolean::CFBoolean, dictionary::CFDictionary, string::CFString};
+use core_foundation_sys::{
+    array::{CFArrayGetCount, CFArrayGetValueAtIndex, CFArrayRef},
+    base::{CFRelease, CFTypeRef},
+    data::{CFDataGetBytePtr, CFDataGetLength, CFDataRef},
+    dictionary::CFDictionaryRef,
+    string::CFStringRef,
+};
 use core_graphics::display::{
     kCGNullWindowID, kCGWindowListOptionAll, CGDirectDisplayID, CGDisplay, CGDisplayMode, CGError,
     CGPoint,
 };
+use core_graphics::geometry::CGSize;
 use image::RgbaImage;
 use objc2::MainThreadMarker;
 use objc2_app_kit::NSScreen;
@@ -13,6 +28,62 @@ use crate::error::{XCapError, XCapResult};
 
 use super::{capture::capture, impl_video_recorder::ImplVideoRecorder};
 
+#[allow(non_camel_case_types)]
+type CGDisplayModeRef = *mut c_void;
+#[allow(non_camel_case_types)]
+type CGColorSpaceRef = *mut c_void;
+#[allow(non_camel_case_types)]
+type CGDisplayStreamRef = *mut c_void;
+#[allow(non_camel_case_types)]
+type CGDisplayStreamUpdateRef = *mut c_void;
+#[allow(non_camel_case_types)]
+type CGDisplayStreamFrameStatus = i32;
+#[allow(non_camel_case_types)]
+type IOSurfaceRef = *mut c_void;
+#[allow(non_camel_case_types)]
+type dispatch_queue_t = *mut c_void;
+
+const K_CG_DISPLAY_STREAM_FRAME_STATUS_FRAME_COMPLETE: CGDisplayStreamFrameStatus = 0;
+// FourCharCode for 'BGRA', i.e. kCVPixelFormatType_32BGRA.
+const K_CV_PIXEL_FORMAT_TYPE_32_BGRA: u32 = 0x4247_5241;
+
+/// Callback invoked with each completed frame from [`ImplMonitor::start_capture_stream`] that
+/// has at least one changed region; completed frames with no dirty rects are not delivered.
+pub type FrameHandler = Box<dyn FnMut(RgbaImage) + Send + 'static>;
+
+/// A display's gamma transfer curve, as read/written by [`ImplMonitor::gamma_ramp`] and
+/// [`ImplMonitor::set_gamma_ramp`].
+#[derive(Debug, Clone)]
+pub struct GammaRamp {
+    pub red: Vec<f32>,
+    pub green: Vec<f32>,
+    pub blue: Vec<f32>,
+}
+
+/// A single display mode, as returned by [`ImplMonitor::video_modes`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VideoMode {
+    pub size: (u32, u32),
+    pub bit_depth: u16,
+    pub refresh_rate_millihertz: u32,
+}
+
+impl VideoMode {
+    /// Like `==`, but treats either side's refresh rate of 0 (reported by some built-in
+    /// panels) as matching any refresh rate. Used to resolve a caller-requested mode against
+    /// the modes a display actually reports; not exposed as `PartialEq` since it isn't
+    /// transitive (two modes can each match a 0Hz mode without matching each other).
+    fn matches(&self, other: &Self) -> bool {
+        if self.size != other.size || self.bit_depth != other.bit_depth {
+            return false;
+        }
+
+        self.refresh_rate_millihertz == 0
+            || other.refresh_rate_millihertz == 0
+            || self.refresh_rate_millihertz == other.refresh_rate_millihertz
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct ImplMonitor {
     pub cg_display: CGDisplay,
@@ -36,6 +107,161 @@ extern "C" {
         displays: *mut CGDirectDisplayID,
         display_count: *mut u32,
     ) -> CGError;
+
+    static kCGDisplayShowDuplicateLowResolutionModes: CFStringRef;
+
+    fn CGDisplayCopyAllDisplayModes(
+        display: CGDirectDisplayID,
+        options: CFDictionaryRef,
+    ) -> CFArrayRef;
+    fn CGDisplayCopyDisplayMode(display: CGDirectDisplayID) -> CGDisplayModeRef;
+    fn CGDisplaySetDisplayMode(
+        display: CGDirectDisplayID,
+        mode: CGDisplayModeRef,
+        options: CFDictionaryRef,
+    ) -> CGError;
+    fn CGDisplayModeRelease(mode: CGDisplayModeRef);
+    fn CGDisplayModeGetPixelWidth(mode: CGDisplayModeRef) -> usize;
+    fn CGDisplayModeGetPixelHeight(mode: CGDisplayModeRef) -> usize;
+    fn CGDisplayModeGetRefreshRate(mode: CGDisplayModeRef) -> f64;
+    fn CGDisplayModeCopyPixelEncoding(mode: CGDisplayModeRef) -> CFStringRef;
+
+    fn CGDisplayCopyColorSpace(display: CGDirectDisplayID) -> CGColorSpaceRef;
+    fn CGColorSpaceCopyICCData(space: CGColorSpaceRef) -> CFDataRef;
+    fn CGColorSpaceCopyICCProfile(space: CGColorSpaceRef) -> CFDataRef;
+
+    static kCGDisplayStreamShowCursor: CFStringRef;
+
+    fn CGDisplayStreamCreateWithDispatchQueue(
+        display: CGDirectDisplayID,
+        output_width: usize,
+        output_height: usize,
+        pixel_format: u32,
+        properties: CFDictionaryRef,
+        queue: dispatch_queue_t,
+        handler: *const c_void,
+    ) -> CGDisplayStreamRef;
+    fn CGDisplayStreamStart(display_stream: CGDisplayStreamRef) -> CGError;
+    fn CGDisplayStreamStop(display_stream: CGDisplayStreamRef) -> CGError;
+    fn CGDisplayStreamUpdateGetRectCount(update_ref: CGDisplayStreamUpdateRef) -> usize;
+
+    fn CGDisplayScreenSize(display: CGDirectDisplayID) -> CGSize;
+
+    fn CGDisplayGammaTableCapacity(display: CGDirectDisplayID) -> u32;
+    fn CGGetDisplayTransferByTable(
+        display: CGDirectDisplayID,
+        capacity: u32,
+        red: *mut f32,
+        green: *mut f32,
+        blue: *mut f32,
+        sample_count: *mut u32,
+    ) -> CGError;
+    fn CGSetDisplayTransferByTable(
+        display: CGDirectDisplayID,
+        table_size: u32,
+        red: *const f32,
+        green: *const f32,
+        blue: *const f32,
+    ) -> CGError;
+    fn CGDisplayRestoreColorSyncSettings();
+}
+
+#[link(name = "IOSurface", kind = "framework")]
+extern "C" {
+    fn IOSurfaceLock(surface: IOSurfaceRef, options: u32, seed: *mut u32) -> i32;
+    fn IOSurfaceUnlock(surface: IOSurfaceRef, options: u32, seed: *mut u32) -> i32;
+    fn IOSurfaceGetBaseAddress(surface: IOSurfaceRef) -> *mut c_void;
+    fn IOSurfaceGetBytesPerRow(surface: IOSurfaceRef) -> usize;
+    fn IOSurfaceGetWidth(surface: IOSurfaceRef) -> usize;
+    fn IOSurfaceGetHeight(surface: IOSurfaceRef) -> usize;
+}
+
+extern "C" {
+    fn dispatch_queue_create(label: *const c_char, attr: *mut c_void) -> dispatch_queue_t;
+    fn dispatch_release(object: dispatch_queue_t);
+}
+
+fn rgba_image_from_io_surface(io_surface: IOSurfaceRef) -> Option<RgbaImage> {
+    const K_IO_SURFACE_LOCK_READ_ONLY: u32 = 1;
+
+    unsafe {
+        if IOSurfaceLock(io_surface, K_IO_SURFACE_LOCK_READ_ONLY, std::ptr::null_mut()) != 0 {
+            return None;
+        }
+
+        let width = IOSurfaceGetWidth(io_surface) as u32;
+        let height = IOSurfaceGetHeight(io_surface) as u32;
+        let bytes_per_row = IOSurfaceGetBytesPerRow(io_surface);
+        let base_address = IOSurfaceGetBaseAddress(io_surface) as *const u8;
+
+        let mut rgba = Vec::with_capacity((width * height * 4) as usize);
+        for row in 0..height as usize {
+            let row_ptr = base_address.add(row * bytes_per_row);
+            for col in 0..width as usize {
+                let bgra = std::slice::from_raw_parts(row_ptr.add(col * 4), 4);
+                rgba.extend_from_slice(&[bgra[2], bgra[1], bgra[0], bgra[3]]);
+            }
+        }
+
+        IOSurfaceUnlock(io_surface, K_IO_SURFACE_LOCK_READ_ONLY, std::ptr::null_mut());
+
+        RgbaImage::from_raw(width, height, rgba)
+    }
+}
+
+/// A live frame stream from [`ImplMonitor::start_capture_stream`]. Dropping it stops the stream.
+pub struct CaptureStream {
+    cg_display_stream: CGDisplayStreamRef,
+    dispatch_queue: dispatch_queue_t,
+    // Keeps the frame-available block (and the `FrameHandler` it closes over) alive for as
+    // long as the stream can still invoke it.
+    _frame_available_block:
+        RcBlock<dyn Fn(CGDisplayStreamFrameStatus, u64, IOSurfaceRef, CGDisplayStreamUpdateRef)>,
+}
+
+unsafe impl Send for CaptureStream {}
+
+impl Drop for CaptureStream {
+    fn drop(&mut self) {
+        unsafe {
+            CGDisplayStreamStop(self.cg_display_stream);
+            CFRelease(self.cg_display_stream as CFTypeRef);
+            dispatch_release(self.dispatch_queue);
+        }
+    }
+}
+
+fn show_duplicate_modes_options() -> CFDictionary<CFString, CFBoolean> {
+    CFDictionary::from_CFType_pairs(&[(
+        unsafe { CFString::wrap_under_get_rule(kCGDisplayShowDuplicateLowResolutionModes) },
+        CFBoolean::true_value(),
+    )])
+}
+
+unsafe fn video_mode_from_cg_display_mode(cg_display_mode: CGDisplayModeRef) -> VideoMode {
+    let size = (
+        CGDisplayModeGetPixelWidth(cg_display_mode) as u32,
+        CGDisplayModeGetPixelHeight(cg_display_mode) as u32,
+    );
+    let refresh_rate_millihertz = (CGDisplayModeGetRefreshRate(cg_display_mode) * 1000.0) as u32;
+
+    let pixel_encoding_ref = CGDisplayModeCopyPixelEncoding(cg_display_mode);
+    let bit_depth = if pixel_encoding_ref.is_null() {
+        32
+    } else {
+        let pixel_encoding = CFString::wrap_under_create_rule(pixel_encoding_ref).to_string();
+        match pixel_encoding.as_str() {
+            "IO16BitDirectPixels" => 16,
+            "IO8BitIndexedPixels" => 8,
+            _ => 32,
+        }
+    };
+
+    VideoMode {
+        size,
+        bit_depth,
+        refresh_rate_millihertz,
+    }
 }
 
 impl ImplMonitor {
@@ -128,6 +354,49 @@ impl ImplMonitor {
         }
     }
 
+    pub fn from_region(x: i32, y: i32, width: u32, height: u32) -> XCapResult<ImplMonitor> {
+        let impl_monitors = ImplMonitor::all()?;
+
+        let region_right = x + width as i32;
+        let region_bottom = y + height as i32;
+
+        let mut best_impl_monitor = None;
+        let mut best_area: i64 = 0;
+
+        for impl_monitor in impl_monitors {
+            let monitor_right = impl_monitor.x + impl_monitor.width as i32;
+            let monitor_bottom = impl_monitor.y + impl_monitor.height as i32;
+
+            let intersection_left = x.max(impl_monitor.x);
+            let intersection_top = y.max(impl_monitor.y);
+            let intersection_right = region_right.min(monitor_right);
+            let intersection_bottom = region_bottom.min(monitor_bottom);
+
+            let intersection_area = if intersection_right > intersection_left
+                && intersection_bottom > intersection_top
+            {
+                (intersection_right - intersection_left) as i64
+                    * (intersection_bottom - intersection_top) as i64
+            } else {
+                0
+            };
+
+            if intersection_area > best_area {
+                best_area = intersection_area;
+                best_impl_monitor = Some(impl_monitor);
+            }
+        }
+
+        if let Some(impl_monitor) = best_impl_monitor {
+            return Ok(impl_monitor);
+        }
+
+        ImplMonitor::all()?
+            .into_iter()
+            .find(|impl_monitor| impl_monitor.is_primary)
+            .ok_or_else(|| XCapError::new("Primary monitor not found"))
+    }
+
     pub fn screen_map() -> Result<HashMap<u32, String>, Error> {
         let mut screen_name_map: HashMap<u32, String> = HashMap::new();
         let thread = MainThreadMarker::new();
@@ -169,4 +438,272 @@ impl ImplMonitor {
     pub fn video_recorder(&self) -> XCapResult<ImplVideoRecorder> {
         ImplVideoRecorder::new()
     }
+
+    pub fn video_modes(&self) -> XCapResult<Vec<VideoMode>> {
+        let options = show_duplicate_modes_options();
+        let cg_display_modes = unsafe {
+            CGDisplayCopyAllDisplayModes(self.cg_display.id, options.as_concrete_TypeRef())
+        };
+
+        if cg_display_modes.is_null() {
+            return Err(XCapError::new("Get display modes failed"));
+        }
+
+        let count = unsafe { CFArrayGetCount(cg_display_modes) };
+        let mut video_modes = Vec::with_capacity(count as usize);
+
+        for i in 0..count {
+            let cg_display_mode =
+                unsafe { CFArrayGetValueAtIndex(cg_display_modes, i) as CGDisplayModeRef };
+            video_modes.push(unsafe { video_mode_from_cg_display_mode(cg_display_mode) });
+        }
+
+        unsafe { CFRelease(cg_display_modes as CFTypeRef) };
+
+        Ok(video_modes)
+    }
+
+    pub fn current_video_mode(&self) -> XCapResult<VideoMode> {
+        let cg_display_mode = unsafe { CGDisplayCopyDisplayMode(self.cg_display.id) };
+
+        if cg_display_mode.is_null() {
+            return Err(XCapError::new("Get current display mode failed"));
+        }
+
+        let video_mode = unsafe { video_mode_from_cg_display_mode(cg_display_mode) };
+        unsafe { CGDisplayModeRelease(cg_display_mode) };
+
+        Ok(video_mode)
+    }
+
+    pub fn set_video_mode(&self, video_mode: &VideoMode) -> XCapResult<()> {
+        let options = show_duplicate_modes_options();
+        let cg_display_modes = unsafe {
+            CGDisplayCopyAllDisplayModes(self.cg_display.id, options.as_concrete_TypeRef())
+        };
+
+        if cg_display_modes.is_null() {
+            return Err(XCapError::new("Get display modes failed"));
+        }
+
+        let count = unsafe { CFArrayGetCount(cg_display_modes) };
+        let mut target_cg_display_mode: CGDisplayModeRef = std::ptr::null_mut();
+
+        for i in 0..count {
+            let cg_display_mode =
+                unsafe { CFArrayGetValueAtIndex(cg_display_modes, i) as CGDisplayModeRef };
+            if unsafe { video_mode_from_cg_display_mode(cg_display_mode) }.matches(video_mode) {
+                target_cg_display_mode = cg_display_mode;
+                break;
+            }
+        }
+
+        let result = if target_cg_display_mode.is_null() {
+            Err(XCapError::new("Requested video mode is not supported"))
+        } else {
+            let cg_error = unsafe {
+                CGDisplaySetDisplayMode(
+                    self.cg_display.id,
+                    target_cg_display_mode,
+                    std::ptr::null(),
+                )
+            };
+
+            if cg_error != 0 {
+                Err(XCapError::CoreGraphicsDisplayCGError(cg_error))
+            } else {
+                Ok(())
+            }
+        };
+
+        unsafe { CFRelease(cg_display_modes as CFTypeRef) };
+
+        result
+    }
+
+    pub fn color_profile(&self) -> XCapResult<Vec<u8>> {
+        let cg_color_space = unsafe { CGDisplayCopyColorSpace(self.cg_display.id) };
+
+        if cg_color_space.is_null() {
+            return Err(XCapError::new("Get display color space failed"));
+        }
+
+        let mut cf_icc_data = unsafe { CGColorSpaceCopyICCData(cg_color_space) };
+        if cf_icc_data.is_null() {
+            // `CGColorSpaceCopyICCData` is unavailable on older systems; fall back to the
+            // deprecated API there.
+            cf_icc_data = unsafe { CGColorSpaceCopyICCProfile(cg_color_space) };
+        }
+
+        let result = if cf_icc_data.is_null() {
+            Err(XCapError::new("Get display ICC profile failed"))
+        } else {
+            let length = unsafe { CFDataGetLength(cf_icc_data) } as usize;
+            let bytes_ptr = unsafe { CFDataGetBytePtr(cf_icc_data) };
+            let icc_profile = unsafe { std::slice::from_raw_parts(bytes_ptr, length) }.to_vec();
+
+            unsafe { CFRelease(cf_icc_data as CFTypeRef) };
+
+            Ok(icc_profile)
+        };
+
+        unsafe { CFRelease(cg_color_space as CFTypeRef) };
+
+        result
+    }
+
+    pub fn start_capture_stream(&self, frame_handler: FrameHandler) -> XCapResult<CaptureStream> {
+        let properties = CFDictionary::from_CFType_pairs(&[(
+            unsafe { CFString::wrap_under_get_rule(kCGDisplayStreamShowCursor) },
+            CFBoolean::true_value(),
+        )]);
+
+        // The frame-available block must be `Fn`, so the `FnMut` handler is kept behind a
+        // mutex rather than called directly.
+        let frame_handler = Mutex::new(frame_handler);
+
+        let frame_available_block = RcBlock::new(
+            move |status: CGDisplayStreamFrameStatus,
+                  _display_time: u64,
+                  io_surface: IOSurfaceRef,
+                  update_ref: CGDisplayStreamUpdateRef| {
+                if status != K_CG_DISPLAY_STREAM_FRAME_STATUS_FRAME_COMPLETE {
+                    return;
+                }
+
+                // `FrameIdle`/`FrameBlank` were dropped above; of the completed frames, only
+                // forward those that actually changed something on screen.
+                if unsafe { CGDisplayStreamUpdateGetRectCount(update_ref) } == 0 {
+                    return;
+                }
+
+                if let Some(rgba_image) = rgba_image_from_io_surface(io_surface) {
+                    (frame_handler.lock().unwrap())(rgba_image);
+                }
+            },
+        );
+
+        // CGDisplayStream expects frames to be delivered one at a time; a concurrent queue
+        // would let the block above run re-entrantly on multiple threads.
+        let label = CString::new("xcap.display_stream").unwrap();
+        let queue = unsafe { dispatch_queue_create(label.as_ptr(), std::ptr::null_mut()) };
+
+        let cg_display_stream = unsafe {
+            CGDisplayStreamCreateWithDispatchQueue(
+                self.cg_display.id,
+                self.width as usize,
+                self.height as usize,
+                K_CV_PIXEL_FORMAT_TYPE_32_BGRA,
+                properties.as_concrete_TypeRef(),
+                queue,
+                &*frame_available_block as *const _ as *const c_void,
+            )
+        };
+
+        if cg_display_stream.is_null() {
+            unsafe { dispatch_release(queue) };
+            return Err(XCapError::new("Create display stream failed"));
+        }
+
+        let cg_error = unsafe { CGDisplayStreamStart(cg_display_stream) };
+        if cg_error != 0 {
+            unsafe {
+                CFRelease(cg_display_stream as CFTypeRef);
+                dispatch_release(queue);
+            }
+            return Err(XCapError::CoreGraphicsDisplayCGError(cg_error));
+        }
+
+        Ok(CaptureStream {
+            dispatch_queue: queue,
+            cg_display_stream,
+            _frame_available_block: frame_available_block,
+        })
+    }
+
+    pub fn physical_size_mm(&self) -> (f64, f64) {
+        const DEFAULT_DPI: f64 = 96.0;
+
+        let cg_size = unsafe { CGDisplayScreenSize(self.cg_display.id) };
+
+        if cg_size.width > 0.0 && cg_size.height > 0.0 {
+            (cg_size.width, cg_size.height)
+        } else {
+            // Some virtual and projector displays report a bogus zero size; derive a
+            // physical size from the pixel dimensions (not `self.width`/`self.height`,
+            // which are logical points) assuming a default 96 DPI instead.
+            let pixel_width = self.width as f64 * self.scale_factor as f64;
+            let pixel_height = self.height as f64 * self.scale_factor as f64;
+
+            (
+                pixel_width / DEFAULT_DPI * 25.4,
+                pixel_height / DEFAULT_DPI * 25.4,
+            )
+        }
+    }
+
+    pub fn dpi(&self) -> f64 {
+        let (width_mm, _) = self.physical_size_mm();
+        let pixel_width = self.width as f64 * self.scale_factor as f64;
+
+        pixel_width / (width_mm / 25.4)
+    }
+
+    pub fn gamma_ramp(&self) -> XCapResult<GammaRamp> {
+        let capacity = unsafe { CGDisplayGammaTableCapacity(self.cg_display.id) };
+
+        let mut red = vec![0f32; capacity as usize];
+        let mut green = vec![0f32; capacity as usize];
+        let mut blue = vec![0f32; capacity as usize];
+        let mut sample_count: u32 = 0;
+
+        let cg_error = unsafe {
+            CGGetDisplayTransferByTable(
+                self.cg_display.id,
+                capacity,
+                red.as_mut_ptr(),
+                green.as_mut_ptr(),
+                blue.as_mut_ptr(),
+                &mut sample_count,
+            )
+        };
+
+        if cg_error != 0 {
+            return Err(XCapError::CoreGraphicsDisplayCGError(cg_error));
+        }
+
+        red.truncate(sample_count as usize);
+        green.truncate(sample_count as usize);
+        blue.truncate(sample_count as usize);
+
+        Ok(GammaRamp { red, green, blue })
+    }
+
+    pub fn set_gamma_ramp(&self, gamma_ramp: &GammaRamp) -> XCapResult<()> {
+        if gamma_ramp.red.len() != gamma_ramp.green.len()
+            || gamma_ramp.red.len() != gamma_ramp.blue.len()
+        {
+            return Err(XCapError::new("Gamma ramp channels must be the same length"));
+        }
+
+        let cg_error = unsafe {
+            CGSetDisplayTransferByTable(
+                self.cg_display.id,
+                gamma_ramp.red.len() as u32,
+                gamma_ramp.red.as_ptr(),
+                gamma_ramp.green.as_ptr(),
+                gamma_ramp.blue.as_ptr(),
+            )
+        };
+
+        if cg_error != 0 {
+            Err(XCapError::CoreGraphicsDisplayCGError(cg_error))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn restore_gamma(&self) {
+        unsafe { CGDisplayRestoreColorSyncSettings() };
+    }
 }
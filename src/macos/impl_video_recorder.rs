@@ -0,0 +1,10 @@
+use crate::error::XCapResult;
+
+#[derive(Debug, Clone)]
+pub(crate) struct ImplVideoRecorder {}
+
+impl ImplVideoRecorder {
+    pub(crate) fn new() -> XCapResult<ImplVideoRecorder> {
+        Ok(ImplVideoRecorder {})
+    }
+}
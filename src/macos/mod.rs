@@ -0,0 +1,7 @@
+mod capture;
+mod impl_monitor;
+mod impl_video_recorder;
+
+pub(crate) use impl_monitor::ImplMonitor;
+pub(crate) use impl_video_recorder::ImplVideoRecorder;
+pub use impl_monitor::{CaptureStream, FrameHandler, GammaRamp, VideoMode};
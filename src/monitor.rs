@@ -0,0 +1,123 @@
+use image::RgbaImage;
+
+use crate::error::XCapResult;
+use crate::macos::{CaptureStream, FrameHandler, GammaRamp, ImplMonitor, VideoMode};
+use crate::video_recorder::VideoRecorder;
+
+#[derive(Debug, Clone)]
+pub struct Monitor {
+    impl_monitor: ImplMonitor,
+}
+
+impl Monitor {
+    pub fn all() -> XCapResult<Vec<Monitor>> {
+        let impl_monitors = ImplMonitor::all()?;
+
+        Ok(impl_monitors
+            .into_iter()
+            .map(|impl_monitor| Monitor { impl_monitor })
+            .collect())
+    }
+
+    pub fn from_point(x: i32, y: i32) -> XCapResult<Monitor> {
+        let impl_monitor = ImplMonitor::from_point(x, y)?;
+
+        Ok(Monitor { impl_monitor })
+    }
+
+    pub fn from_region(x: i32, y: i32, width: u32, height: u32) -> XCapResult<Monitor> {
+        let impl_monitor = ImplMonitor::from_region(x, y, width, height)?;
+
+        Ok(Monitor { impl_monitor })
+    }
+
+    pub fn id(&self) -> u32 {
+        self.impl_monitor.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.impl_monitor.name
+    }
+
+    pub fn x(&self) -> i32 {
+        self.impl_monitor.x
+    }
+
+    pub fn y(&self) -> i32 {
+        self.impl_monitor.y
+    }
+
+    pub fn width(&self) -> u32 {
+        self.impl_monitor.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.impl_monitor.height
+    }
+
+    pub fn rotation(&self) -> f32 {
+        self.impl_monitor.rotation
+    }
+
+    pub fn scale_factor(&self) -> f32 {
+        self.impl_monitor.scale_factor
+    }
+
+    pub fn frequency(&self) -> f32 {
+        self.impl_monitor.frequency
+    }
+
+    pub fn is_primary(&self) -> bool {
+        self.impl_monitor.is_primary
+    }
+
+    pub fn capture_image(&self) -> XCapResult<RgbaImage> {
+        self.impl_monitor.capture_image()
+    }
+
+    pub fn video_recorder(&self) -> XCapResult<VideoRecorder> {
+        self.impl_monitor.video_recorder()?;
+
+        Ok(VideoRecorder)
+    }
+
+    pub fn video_modes(&self) -> XCapResult<Vec<VideoMode>> {
+        self.impl_monitor.video_modes()
+    }
+
+    pub fn current_video_mode(&self) -> XCapResult<VideoMode> {
+        self.impl_monitor.current_video_mode()
+    }
+
+    pub fn set_video_mode(&self, video_mode: &VideoMode) -> XCapResult<()> {
+        self.impl_monitor.set_video_mode(video_mode)
+    }
+
+    pub fn color_profile(&self) -> XCapResult<Vec<u8>> {
+        self.impl_monitor.color_profile()
+    }
+
+    pub fn start_capture_stream(&self, frame_handler: FrameHandler) -> XCapResult<CaptureStream> {
+        self.impl_monitor.start_capture_stream(frame_handler)
+    }
+
+    pub fn physical_size_mm(&self) -> (f64, f64) {
+        self.impl_monitor.physical_size_mm()
+    }
+
+    pub fn dpi(&self) -> f64 {
+        self.impl_monitor.dpi()
+    }
+
+    pub fn gamma_ramp(&self) -> XCapResult<GammaRamp> {
+        self.impl_monitor.gamma_ramp()
+    }
+
+    pub fn set_gamma_ramp(&self, gamma_ramp: &GammaRamp) -> XCapResult<()> {
+        self.impl_monitor.set_gamma_ramp(gamma_ramp)
+    }
+
+    pub fn restore_gamma(&self) {
+        self.impl_monitor.restore_gamma()
+    }
+}
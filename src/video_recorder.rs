@@ -0,0 +1,4 @@
+/// A video recorder handle, returned by [`crate::Monitor::video_recorder`]. It holds no state
+/// and does not record anything.
+#[derive(Debug, Clone, Copy)]
+pub struct VideoRecorder;